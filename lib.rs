@@ -49,7 +49,7 @@ fn cancelable_sum(values: &[i32], ct: &CancellationToken) -> Result<i32, Operati
 fn main() {
     let cts = CancellationTokenSource::new();
     cts.cancel_after(time::Duration::from_millis(1500));
-    assert_eq!(Err(OperationCanceled), cancelable_sum(&[1,2,3], &cts));
+    assert_eq!(Err(OperationCanceled::new()), cancelable_sum(&[1,2,3], &cts));
 }
 ```
 
@@ -78,7 +78,7 @@ fn cancelable_sleep(dur: Duration, ct: &CancellationToken) -> Result<(), Operati
         // deregistered the on_cancel callback.
         // We use a park() call with 0s timeout to consume the left-over parking token, if any.
         thread::park_timeout(Duration::from_secs(0));
-        Err(OperationCanceled)
+        Err(OperationCanceled::new())
     } else {
         Ok(())
     }
@@ -87,19 +87,34 @@ fn cancelable_sleep(dur: Duration, ct: &CancellationToken) -> Result<(), Operati
 fn main() {
     let cts = CancellationTokenSource::new();
     cts.cancel_after(Duration::from_millis(250));
-    assert_eq!(Err(OperationCanceled), cancelable_sleep(Duration::from_secs(10), &cts));
+    assert_eq!(Err(OperationCanceled::new()), cancelable_sleep(Duration::from_secs(10), &cts));
 }
 ```
 
 **/
 
 use std::{fmt, ops, mem, ptr, io, error, time, thread};
-use std::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
-use std::sync::{Arc, Mutex};
+use std::any::Any;
+use std::sync::atomic::{AtomicUsize, AtomicPtr, ATOMIC_USIZE_INIT, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::marker::PhantomData;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
 
 #[repr(C)]
 pub struct CancellationTokenSource {
-    token: Arc<CancellationToken>
+    token: Arc<CancellationToken>,
+    // Registrations on parent tokens that cause this source to be canceled when
+    // a parent is canceled (see `child`/`child_source`). They are unlinked from
+    // the parents when this source is dropped, so that a long-lived parent does
+    // not accumulate dead children. Empty for a root source.
+    links: Vec<OwnedRegistration>
 }
 
 const STATUS_CANNOT_BE_CANCELED : usize = 0;
@@ -118,12 +133,18 @@ pub struct CancellationToken {
     // The mutex also ensures that `CancellationToken::run()` can't return while the on_cancel callback is still running.
     // The option around the mutex allows us to construct the NO_CANCELLATION token.
     
-    // The `*mut Registration` points to the first active registration.
+    // The `*mut RegistrationNode` points to the first active registration.
     // Registrations are connected in a double-linked-list in order to
     // support O(1) removal.
-    // The back-link (`Registration::link_to_this`) is of type `*mut *mut Registration`
-    // and may refer to the contents of this mutex (for the first node) or the `Registration::next` of the previous node.
-    registrations: Option<Mutex<*mut Registration<'static>>>
+    // The back-link (`RegistrationNode::link_to_this`) is of type `*mut *mut RegistrationNode`
+    // and may refer to the contents of this mutex (for the first node) or the `RegistrationNode::next` of the previous node.
+    registrations: Option<Mutex<*mut RegistrationNode<'static>>>,
+    // Reason that was attached to the cancellation, if any.
+    // Set to a heap-allocated `CancelReason` by `cancel_with_reason` (published
+    // with `Release` before the status becomes observable as canceled) and read
+    // back by `reason()` with `Acquire`. Null while not canceled or when no
+    // reason was supplied. Freed in `Drop`.
+    reason: AtomicPtr<CancelReason>
 }
 
 // AtomicUsize and Mutex are both Sync;
@@ -133,14 +154,87 @@ unsafe impl Send for CancellationToken {}
 
 static NO_CANCELLATION: CancellationToken = CancellationToken {
     status: ATOMIC_USIZE_INIT, //AtomicUsize::new(STATUS_CANNOT_BE_CANCELED),
-    registrations: None
+    registrations: None,
+    reason: AtomicPtr::new(ptr::null_mut())
 };
 
-/// Unit struct used to indicate that an operation was canceled.
+/// The reason a `CancellationTokenSource` was canceled.
 ///
-/// Usually used as `Result<T, OperationCanceled>`.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct OperationCanceled;
+/// Attached with `CancellationTokenSource::cancel_with_reason` and read back
+/// through `CancellationToken::reason`. `Custom` carries an arbitrary payload so
+/// callers can map cancellation to domain-specific errors.
+pub enum CancelReason {
+    /// Cancellation was requested explicitly (e.g. a user abort).
+    UserRequested,
+    /// The operation timed out, as set by `cancel_after`.
+    TimedOut,
+    /// An application-specific cause.
+    Custom(Box<dyn Any + Send + Sync>)
+}
+
+impl CancelReason {
+    /// Maps this reason to the `io::ErrorKind` used by the
+    /// `From<OperationCanceled>` conversion and similar code.
+    pub fn io_error_kind(&self) -> io::ErrorKind {
+        match *self {
+            CancelReason::TimedOut => io::ErrorKind::TimedOut,
+            _ => io::ErrorKind::Interrupted
+        }
+    }
+}
+
+impl fmt::Debug for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CancelReason::UserRequested => f.write_str("UserRequested"),
+            CancelReason::TimedOut => f.write_str("TimedOut"),
+            CancelReason::Custom(_) => f.write_str("Custom(..)")
+        }
+    }
+}
+
+/// Value used to indicate that an operation was canceled.
+///
+/// Usually used as `Result<T, OperationCanceled>`. When the cancellation was
+/// triggered with `CancellationTokenSource::cancel_with_reason`, the cause is
+/// carried along and can be retrieved with `reason()`, so a caller holding only
+/// the `Err` can still react to the cancellation cause.
+///
+/// Two `OperationCanceled` values always compare equal regardless of the carried
+/// reason, so existing `assert_eq!(Err(OperationCanceled::new()), ...)` style
+/// checks keep working.
+///
+/// Note: carrying the reason (an `Arc`) means this type is no longer `Copy` as
+/// it was before the reason was added. It remains `Clone`, so callers that
+/// copied the value can `.clone()` it instead.
+#[derive(Clone, Debug, Default)]
+pub struct OperationCanceled {
+    // The cause, shared with the token (see `CancellationToken::reason`).
+    reason: Option<Arc<CancelReason>>
+}
+
+impl OperationCanceled {
+    /// Creates an `OperationCanceled` without an attached reason.
+    pub fn new() -> OperationCanceled {
+        OperationCanceled { reason: None }
+    }
+
+    /// Returns the cause of the cancellation, if one was attached via
+    /// `CancellationTokenSource::cancel_with_reason`.
+    pub fn reason(&self) -> Option<&CancelReason> {
+        self.reason.as_ref().map(|r| &**r)
+    }
+}
+
+impl PartialEq for OperationCanceled {
+    // Equality ignores the carried reason: an `OperationCanceled` is only ever
+    // used to signal "the operation was canceled".
+    fn eq(&self, _other: &OperationCanceled) -> bool {
+        true
+    }
+}
+
+impl Eq for OperationCanceled {}
 
 // Helper trait for Option<C> where C:FnOnce()
 trait FnOnceOption {
@@ -157,27 +251,307 @@ impl<C> FnOnceOption for Option<C> where C: FnOnce() {
 /// Registrations are the entries in the linked list of on_cancel callbacks.
 /// They are unsafely shared across threads.
 /// Access is synchronized using the cancellation token's mutex.
-struct Registration<'a> {
+struct RegistrationNode<'a> {
     on_cancel: &'a mut (FnOnceOption + Send + 'a),
     cancellation_token: &'a CancellationToken,
     // Next registration in the linked list.
-    next: *mut Registration<'static>,
+    next: *mut RegistrationNode<'static>,
     // Link to the previous node's next field.
     // For the first node, this points to the contents of the CancellationToken::registration mutex.
     // The address of the pointed-to-field is stable:
     // Registrations are never moved (they only exist in `CancellationToken::run()`'s stack frame);
     // and we know the CancellationToken cannot move because it's being borrowed by this registration.
-    link_to_this: *mut *mut Registration<'static>
+    link_to_this: *mut *mut RegistrationNode<'static>
 }
 
-unsafe fn erase_lifetime(r: &mut Registration) -> *mut Registration<'static> {
+unsafe fn erase_lifetime(r: &mut RegistrationNode) -> *mut RegistrationNode<'static> {
     mem::transmute(r)
 }
 
+/// Insert `node` at the front of the linked list whose head pointer is stored at `head`.
+/// May only be called while the registration mutex is acquired.
+/// Unlike the original by-hand insertion in `run()`, this also fixes up the
+/// back-link of the node that is pushed down, so that registrations may be
+/// removed in any order (as required by the heap-allocated `OwnedRegistration`s,
+/// which -- unlike `run()`'s stack registrations -- are not dropped in LIFO order).
+unsafe fn link_front(head: *mut *mut RegistrationNode<'static>, node: *mut RegistrationNode<'static>) {
+    (*node).next = *head;
+    (*node).link_to_this = head;
+    if !(*node).next.is_null() {
+        (*(*node).next).link_to_this = &mut (*node).next;
+    }
+    *head = node;
+}
+
+/// Helper trait object holding an owned `on_cancel` callback for an
+/// `OwnedRegistration`. Boxed so it has a stable address while the registration
+/// is linked into the token's list.
+struct BoxedOnCancel(Option<Box<FnOnce() + Send + 'static>>);
+
+impl FnOnceOption for BoxedOnCancel {
+    fn call_once(&mut self) -> Option<()> {
+        self.0.take().map(|c| c())
+    }
+}
+
+/// An owned entry in a token's registration list.
+///
+/// Unlike the borrow-based `RegistrationNode` used by `run()`, this keeps the
+/// callback and the token alive on the heap, so the registration can outlive
+/// the stack frame that created it. The heap-allocated node is unlinked by its
+/// own `Drop` impl (the same one used by `run()`), which acquires the token
+/// mutex -- so a concurrent `cancel()` is serialized against the unlink.
+///
+/// Field drop order matters: `node` is dropped first (it unlinks itself, only
+/// touching `token`), then `on_cancel`, then `token`.
+struct OwnedRegistration {
+    #[allow(dead_code)]
+    node: Box<RegistrationNode<'static>>,
+    // Referenced by `node.on_cancel`; kept alive until after `node` is dropped.
+    #[allow(dead_code)]
+    on_cancel: Box<BoxedOnCancel>,
+    // Referenced by `node.cancellation_token`; kept alive until after `node` is dropped.
+    #[allow(dead_code)]
+    token: Arc<CancellationToken>
+}
+
+// Like `CancellationToken`, the raw pointers inside the node are only accessed
+// while the token mutex is held, so sharing an `OwnedRegistration` is safe.
+unsafe impl Send for OwnedRegistration {}
+unsafe impl Sync for OwnedRegistration {}
+
+/// An owned handle to an `on_cancel` callback registered via
+/// `CancellationToken::register`.
+///
+/// The callback stays registered until the `Registration` is dropped; dropping
+/// it unlinks the callback (exactly like the end of a `run()` scope). This lets
+/// a cancellation callback outlive the stack frame that created it, unlike the
+/// scope-bound `run()` method.
+///
+/// A handle is inert (its drop does nothing) when the token could never be
+/// canceled, or when the token was already canceled at registration time and the
+/// callback therefore ran immediately on the registering thread.
+pub struct Registration {
+    // `None` for an inert handle; see the type-level documentation.
+    #[allow(dead_code)]
+    node: Option<OwnedRegistration>
+}
+
+/// A borrowed counterpart of `OwnedRegistration`, used by the `cancelled()`
+/// future. Instead of keeping the token alive through an `Arc`, it borrows the
+/// token for `'a`; the caller guarantees the token outlives the registration.
+///
+/// As with `OwnedRegistration`, dropping `node` first unlinks the entry from the
+/// token's list (via the shared `Drop for RegistrationNode`).
+#[cfg(feature = "async")]
+struct BorrowedRegistration<'a> {
+    #[allow(dead_code)]
+    node: Box<RegistrationNode<'static>>,
+    // Referenced by `node.on_cancel`; kept alive until after `node` is dropped.
+    #[allow(dead_code)]
+    on_cancel: Box<BoxedOnCancel>,
+    marker: PhantomData<&'a CancellationToken>
+}
+
+#[cfg(feature = "async")]
+unsafe impl<'a> Send for BorrowedRegistration<'a> {}
+#[cfg(feature = "async")]
+unsafe impl<'a> Sync for BorrowedRegistration<'a> {}
+
+/// Shared slot holding the `Waker` of the task waiting on a cancellation future.
+///
+/// The future stores the current `Waker` here on every poll; the `on_cancel`
+/// callback takes it and wakes the task. The `Mutex` serializes those two
+/// accesses, since the callback runs on the canceling thread.
+#[cfg(feature = "async")]
+struct WaitState {
+    waker: Mutex<Option<Waker>>
+}
+
+#[cfg(feature = "async")]
+impl WaitState {
+    fn new() -> Arc<WaitState> {
+        Arc::new(WaitState { waker: Mutex::new(None) })
+    }
+
+    /// The `on_cancel` callback: wake the stored task, if any.
+    fn wake(state: &Arc<WaitState>) -> impl FnOnce() + Send + 'static {
+        let state = state.clone();
+        move || {
+            if let Some(waker) = state.waker.lock().unwrap().take() {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Stores `cx`'s waker and reports whether the future should resolve. The
+    /// re-check of `is_canceled()` after storing the waker closes the race where
+    /// cancellation fires between the caller's initial check and this store.
+    fn poll(&self, token: &CancellationToken, cx: &Context) -> Poll<()> {
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+        if token.is_canceled() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+/// Future returned by `CancellationToken::cancelled`. Resolves when the token is
+/// canceled, or immediately if it already is.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitForCancellation<'a> {
+    token: &'a CancellationToken,
+    state: Arc<WaitState>,
+    registration: Option<BorrowedRegistration<'a>>,
+    registered: bool
+}
+
+#[cfg(feature = "async")]
+impl<'a> Future for WaitForCancellation<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        if this.token.is_canceled() {
+            return Poll::Ready(());
+        }
+        let poll = this.state.poll(this.token, cx);
+        if !this.registered {
+            this.registered = true;
+            this.registration = this.token.register_borrowed(Box::new(WaitState::wake(&this.state)));
+            // `register_borrowed` may have run the callback immediately; re-read
+            // the status so we don't return `Pending` on an already-canceled token.
+            if this.token.is_canceled() {
+                return Poll::Ready(());
+            }
+        }
+        poll
+    }
+}
+
+/// Owned (`'static`) variant of `WaitForCancellation`, returned by
+/// `CancellationToken::cancelled_owned`. Keeps the token alive through an `Arc`
+/// so it can be held across tasks that outlive the `CancellationTokenSource`.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitForCancellationOwned {
+    token: Arc<CancellationToken>,
+    state: Arc<WaitState>,
+    registration: Option<Registration>,
+    registered: bool
+}
+
+#[cfg(feature = "async")]
+impl Future for WaitForCancellationOwned {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let this = self.get_mut();
+        if this.token.is_canceled() {
+            return Poll::Ready(());
+        }
+        let poll = this.state.poll(&this.token, cx);
+        if !this.registered {
+            this.registered = true;
+            this.registration = Some(this.token.register(WaitState::wake(&this.state)));
+            if this.token.is_canceled() {
+                return Poll::Ready(());
+            }
+        }
+        poll
+    }
+}
+
+/// Future combinator that wraps an inner future together with a
+/// `CancellationToken` and completes early with `Err(OperationCanceled)` as soon
+/// as the token is canceled. Created via [`CancelFutureExt::or_cancel`].
+///
+/// If the inner future completes first, its output is returned as `Ok`. Once the
+/// combinator has completed (either way), polling it again panics, like any
+/// fused future.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Cancelable<F>(CancelableState<F>);
+
+/// Private state machine behind `Cancelable`. Keeping it private means neither
+/// the states nor their fields are observable or constructible by users, so the
+/// fused-future invariant cannot be broken from the outside.
+#[cfg(feature = "async")]
+enum CancelableState<F> {
+    // The inner future is still running and the token is not yet canceled.
+    Pending {
+        future: F,
+        state: Arc<WaitState>,
+        token: Arc<CancellationToken>,
+        registration: Option<Registration>
+    },
+    // The combinator has produced its output; polling again panics.
+    Terminated
+}
+
+#[cfg(feature = "async")]
+impl<F: Future> Future for Cancelable<F> {
+    type Output = Result<F::Output, OperationCanceled>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // Safety: we never move out of `future` while in the `Pending` state;
+        // the only mutation of the enum discriminant replaces the whole value
+        // with `Terminated`, dropping (never moving) the inner future in place.
+        let this = unsafe { &mut self.get_unchecked_mut().0 };
+        let result = match *this {
+            CancelableState::Terminated => panic!("Cancelable polled after completion"),
+            CancelableState::Pending { ref mut future, ref state, ref token, ref mut registration } => {
+                let inner = unsafe { Pin::new_unchecked(future) };
+                match inner.poll(cx) {
+                    Poll::Ready(value) => Ok(value),
+                    Poll::Pending => {
+                        // Refresh the stored waker, then register once.
+                        *state.waker.lock().unwrap() = Some(cx.waker().clone());
+                        if registration.is_none() {
+                            *registration = Some(token.register(WaitState::wake(state)));
+                        }
+                        // Re-check after registering so a cancellation that raced
+                        // with the store above is not missed.
+                        if token.is_canceled() {
+                            Err(token.operation_canceled())
+                        } else {
+                            return Poll::Pending;
+                        }
+                    }
+                }
+            }
+        };
+        // Drop the inner future (and its registration) and fuse.
+        *this = CancelableState::Terminated;
+        Poll::Ready(result)
+    }
+}
+
+/// Extension trait adding [`or_cancel`](CancelFutureExt::or_cancel) to every
+/// future.
+#[cfg(feature = "async")]
+pub trait CancelFutureExt: Future + Sized {
+    /// Wraps this future so that it completes with `Err(OperationCanceled)` if
+    /// `ct` is canceled before the future finishes.
+    fn or_cancel(self, ct: &Arc<CancellationToken>) -> Cancelable<Self> {
+        Cancelable(CancelableState::Pending {
+            future: self,
+            state: WaitState::new(),
+            token: ct.clone(),
+            registration: None
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<F: Future> CancelFutureExt for F {}
+
 /// Remove registration from the linked list of registrations.
 /// May only be called while the registration mutex is acquired.
 /// Assumes that r.link_to_this is not null.
-unsafe fn unlink(r: &mut Registration) {
+unsafe fn unlink(r: &mut RegistrationNode) {
     assert!(*r.link_to_this == erase_lifetime(r));
     // let previous node point to the next:
     *r.link_to_this = r.next;
@@ -195,9 +569,57 @@ impl CancellationTokenSource {
         CancellationTokenSource {
             token: Arc::new(CancellationToken {
                 status: AtomicUsize::new(STATUS_NOT_CANCELED),
-                registrations: Some(Mutex::new(ptr::null_mut()))
-            })
+                registrations: Some(Mutex::new(ptr::null_mut())),
+                reason: AtomicPtr::new(ptr::null_mut())
+            }),
+            links: Vec::new()
+        }
+    }
+
+    /// Creates a new `CancellationTokenSource` whose token is canceled when
+    /// either this source is canceled or the returned source is canceled
+    /// directly.
+    ///
+    /// Cancelling or dropping the child has no effect on this source.
+    /// If this source is already canceled, the returned child is created
+    /// already canceled.
+    ///
+    /// The registration created in this source's token is unlinked when the
+    /// child is dropped, so that a long-lived parent does not accumulate dead
+    /// children.
+    pub fn child(&self) -> CancellationTokenSource {
+        self.token.child_source()
+    }
+
+    /// Creates a new `CancellationTokenSource` whose token is canceled as soon
+    /// as any of the given `parents` is canceled (or the returned source is
+    /// canceled directly).
+    ///
+    /// Parents that can never be canceled are ignored. If any parent is already
+    /// canceled, the returned source is created already canceled and no further
+    /// parents are wired up.
+    ///
+    /// As with `child`, the registrations created in the parents are unlinked
+    /// when the returned source is dropped.
+    pub fn linked(parents: &[&Arc<CancellationToken>]) -> CancellationTokenSource {
+        let token = CancellationTokenSource::new().token;
+        let mut links = Vec::new();
+        for parent in parents {
+            let child_token = token.clone();
+            match parent.register_owned(Box::new(move || child_token.cancel())) {
+                Some(reg) => links.push(reg),
+                None => {
+                    // `register_owned` returns `None` either because the parent
+                    // can never be canceled (nothing to do) or because it was
+                    // already canceled -- in which case the callback has just
+                    // canceled our token and we can stop wiring up parents.
+                    if token.is_canceled() {
+                        break;
+                    }
+                }
+            }
         }
+        CancellationTokenSource { token: token, links: links }
     }
 
     /// Gets the token managed by this CancellationTokenSource.
@@ -217,13 +639,40 @@ impl CancellationTokenSource {
         self.token.cancel()
     }
 
+    /// Marks the cancellation token as canceled and records `reason` as the
+    /// cause, which can later be retrieved with `CancellationToken::reason`.
+    ///
+    /// Like `cancel`, this has no effect if the token was already canceled; in
+    /// particular the reason of an already-canceled token is not overwritten.
+    pub fn cancel_with_reason(&self, reason: CancelReason) {
+        self.token.cancel_with_reason(reason)
+    }
+
+    /// Attempts to cancel the token without blocking.
+    ///
+    /// Unlike `cancel`, this never waits on the registration mutex: if another
+    /// thread is concurrently cancelling the token or manipulating its
+    /// registrations, this returns `false` immediately. Returns `true` if this
+    /// call transitioned the token to canceled, and `false` if the token was
+    /// already canceled or the attempt could not proceed without blocking.
+    pub fn try_cancel(&self) -> bool {
+        self.token.try_cancel(None)
+    }
+
+    /// Like `try_cancel`, but records `reason` as the cancellation cause when it
+    /// succeeds. The `reason` is consumed regardless of the outcome.
+    pub fn try_cancel_with_reason(&self, reason: CancelReason) -> bool {
+        self.token.try_cancel(Some(reason))
+    }
+
     /// Creates a new, detached thread that waits for the specified duration
-    /// and then marks the cancellation token as canceled.
+    /// and then marks the cancellation token as canceled with the
+    /// `CancelReason::TimedOut` cause.
     pub fn cancel_after(&self, dur: time::Duration) {
         let token = self.token.clone();
         thread::spawn(move || {
             thread::sleep(dur);
-            token.cancel()
+            token.cancel_with_reason(CancelReason::TimedOut)
         });
     }
 }
@@ -258,30 +707,72 @@ impl CancellationToken {
     }
 
     /// Returns `Ok(())` if this token has not been canceled.
-    /// Returns `Err(OperationCanceled)` if this token has been canceled.
+    /// Returns `Err(OperationCanceled)` if this token has been canceled; the
+    /// returned error carries the cancellation reason (if any), retrievable with
+    /// `OperationCanceled::reason`.
     ///
     /// This is an alternative to `is_canceled()` that can be
     /// used with the `try!()` macro.
     #[inline]
     pub fn result(&self) -> Result<(), OperationCanceled> {
         if self.is_canceled() {
-            Err(OperationCanceled)
+            Err(self.operation_canceled())
         } else {
             Ok(())
         }
     }
 
     fn cancel(&self) {
+        self.cancel_internal(None);
+    }
+
+    fn cancel_with_reason(&self, reason: CancelReason) {
+        self.cancel_internal(Some(reason));
+    }
+
+    fn cancel_internal(&self, reason: Option<CancelReason>) {
         if self.is_canceled() {
             // avoid deadlock if cancel() is called within on_cancel callback
             return;
         }
         let mut registrations = self.registrations.as_ref().unwrap().lock().unwrap();
+        self.cancel_locked(&mut *registrations, reason);
+    }
+
+    /// Non-blocking variant of `cancel_internal`: if the registration mutex is
+    /// currently held (by another `cancel()`, or a `run()`/`register()` on this
+    /// token) this returns `false` instead of blocking. Returns `true` only when
+    /// this call actually transitioned the token to canceled.
+    fn try_cancel(&self, reason: Option<CancelReason>) -> bool {
+        if self.is_canceled() {
+            return false;
+        }
+        match self.registrations.as_ref().unwrap().try_lock() {
+            Ok(mut registrations) => self.cancel_locked(&mut *registrations, reason),
+            Err(TryLockError::WouldBlock) => false,
+            Err(TryLockError::Poisoned(e)) => panic!("{}", e)
+        }
+    }
+
+    /// Performs the cancellation while the registration mutex is held.
+    /// Returns `false` if the token was already canceled. The mutex guard is
+    /// passed by reference so the different entry points can share this body.
+    fn cancel_locked(&self, registrations: &mut *mut RegistrationNode<'static>,
+                     reason: Option<CancelReason>) -> bool {
         let status = self.status.load(Ordering::Relaxed);
         if status == STATUS_CANCELED {
-            return; // already canceled
+            return false; // already canceled
         }
         assert!(status == STATUS_NOT_CANCELED);
+        if let Some(reason) = reason {
+            // Publish the reason before the status transition, so any thread
+            // that observes the cancellation (via `Acquire`) can also read the
+            // reason. We won the race for STATUS_NOT_CANCELED, so the slot is
+            // still null and nobody else will write it. The `Arc` lets the
+            // reason be shared with `OperationCanceled` values.
+            let reason = Arc::into_raw(Arc::new(reason)) as *mut CancelReason;
+            self.reason.store(reason, Ordering::Release);
+        }
         self.status.store(STATUS_CANCELING, Ordering::Release);
         while !registrations.is_null() {
             unsafe {
@@ -291,6 +782,49 @@ impl CancellationToken {
             }
         }
         self.status.store(STATUS_CANCELED, Ordering::Release);
+        true
+    }
+
+    /// Returns the reason this token was canceled, if one was supplied via
+    /// `CancellationTokenSource::cancel_with_reason`.
+    ///
+    /// Returns `None` for a token that has not been canceled, or that was
+    /// canceled without a reason (e.g. through a plain `cancel()`).
+    pub fn reason(&self) -> Option<&CancelReason> {
+        let ptr = self.reason.load(Ordering::Acquire);
+        if ptr.is_null() {
+            None
+        } else {
+            // The pointed-to reason is allocated once and never moved or freed
+            // until the token itself is dropped, so the borrow is valid for the
+            // lifetime of `&self`.
+            Some(unsafe { &*ptr })
+        }
+    }
+
+    /// Returns a shared handle to the cancellation reason, cloning the `Arc` so
+    /// the reason can be carried in an `OperationCanceled` that outlives a borrow
+    /// of the token.
+    fn reason_arc(&self) -> Option<Arc<CancelReason>> {
+        let ptr = self.reason.load(Ordering::Acquire);
+        if ptr.is_null() {
+            None
+        } else {
+            unsafe {
+                // Reconstruct the `Arc` owned by the slot, clone it for the
+                // caller, then `forget` the reconstructed one so the slot keeps
+                // its strong reference.
+                let owned = Arc::from_raw(ptr as *const CancelReason);
+                let shared = owned.clone();
+                mem::forget(owned);
+                Some(shared)
+            }
+        }
+    }
+
+    /// Builds an `OperationCanceled` carrying this token's cancellation reason.
+    fn operation_canceled(&self) -> OperationCanceled {
+        OperationCanceled { reason: self.reason_arc() }
     }
 
     /// Runs function `f` on the current thread.
@@ -321,14 +855,14 @@ impl CancellationToken {
     {
         let mut on_cancel = Some(on_cancel);
         // Create a dummy registration
-        let mut registration: Option<Registration> = None;
+        let mut registration: Option<RegistrationNode> = None;
 
         // Initialization part is extracted into new function so that it doesn't get
         // unnecessarily monomorphized.
         fn init_registration<'a>(
                 token: &'a CancellationToken,
                 on_cancel: &'a mut (FnOnceOption + Send + 'a),
-                registration: &mut Option<Registration<'a>>)
+                registration: &mut Option<RegistrationNode<'a>>)
         {
             // Check the status before acquiring the lock.
             // This is important to avoid deadlocks when the token is re-used within an on_cancel callback.
@@ -340,17 +874,18 @@ impl CancellationToken {
                     match token.status.load(Ordering::Relaxed) {
                         STATUS_NOT_CANCELED => {
                             // Insert registration into linked list
-                            let first_registration: &mut *mut Registration = &mut *mutex_guard;
-                            *registration = Some(Registration {
+                            let first_registration: *mut *mut RegistrationNode = &mut *mutex_guard;
+                            *registration = Some(RegistrationNode {
                                 on_cancel: on_cancel,
                                 cancellation_token: token,
-                                next: *first_registration,
-                                link_to_this: first_registration
+                                next: ptr::null_mut(),
+                                link_to_this: ptr::null_mut()
                             });
                             // Erasing the lifetime of the registration is safe,
                             // because the Drop impl of the registration will undo this assignment
                             // before on_cancel is dropped.
-                            *first_registration = unsafe { erase_lifetime(registration.as_mut().unwrap()) };
+                            let node = unsafe { erase_lifetime(registration.as_mut().unwrap()) };
+                            unsafe { link_front(first_registration, node); }
                         },
                         STATUS_CANCELED => {
                             // if already canceled, run the on_cancel callback immediately
@@ -377,7 +912,7 @@ impl CancellationToken {
         return f();
 
         // The registration will be dropped automatically here
-        impl <'a> Drop for Registration<'a> {
+        impl <'a> Drop for RegistrationNode<'a> {
             fn drop(&mut self) {
                 let _mutex_guard = self.cancellation_token.registrations.as_ref().unwrap().lock().unwrap();
                 if !self.link_to_this.is_null() {
@@ -386,6 +921,155 @@ impl CancellationToken {
             }
         }
     }
+
+    /// Shared core of `register_owned`/`register_borrowed`: status
+    /// check/lock/re-check/link dance common to both, returning the linked
+    /// node and the wrapped callback so each caller can build its own handle
+    /// type (`OwnedRegistration` vs `BorrowedRegistration`) around them.
+    ///
+    /// Returns `None` (and does not allocate a node) when the token can never be
+    /// canceled, or when the token is already canceled -- in which case the
+    /// callback is executed immediately on the calling thread.
+    fn register_node(token: &CancellationToken, on_cancel: Box<FnOnce() + Send + 'static>)
+        -> Option<(Box<RegistrationNode<'static>>, Box<BoxedOnCancel>)>
+    {
+        // Check the status before acquiring the lock, mirroring `run()`, so we
+        // don't deadlock when the token is re-used within an on_cancel callback.
+        match token.status.load(Ordering::Acquire) {
+            STATUS_CANNOT_BE_CANCELED => return None,
+            STATUS_CANCELING | STATUS_CANCELED => {
+                // Already canceled: run the callback immediately.
+                on_cancel();
+                return None;
+            }
+            _ => {}
+        }
+        let mut on_cancel = Box::new(BoxedOnCancel(Some(on_cancel)));
+        let mut mutex_guard = token.registrations.as_ref().unwrap().lock().unwrap();
+        // The status might have changed while we waited for the lock.
+        if token.status.load(Ordering::Relaxed) != STATUS_NOT_CANCELED {
+            mem::drop(mutex_guard);
+            on_cancel.call_once();
+            return None;
+        }
+        let mut node = Box::new(RegistrationNode {
+            // These references point into `on_cancel`/`token`, which the caller
+            // keeps alive alongside `node`, dropped only after `node` has
+            // unlinked itself.
+            on_cancel: {
+                let r: &mut (FnOnceOption + Send) = &mut *on_cancel;
+                unsafe { mem::transmute(r) }
+            },
+            cancellation_token: unsafe { mem::transmute(token) },
+            next: ptr::null_mut(),
+            link_to_this: ptr::null_mut()
+        });
+        let head: *mut *mut RegistrationNode = &mut *mutex_guard;
+        let node_ptr = unsafe { erase_lifetime(&mut *node) };
+        unsafe { link_front(head, node_ptr); }
+        mem::drop(mutex_guard);
+        Some((node, on_cancel))
+    }
+
+    /// Registers an owned `on_cancel` callback on a heap-allocated node and
+    /// returns the node so the caller can keep it alive.
+    ///
+    /// This is the owned counterpart of `run()`: the callback lives on the heap
+    /// and remains registered until the returned `OwnedRegistration` is dropped.
+    ///
+    /// Returns `None` (and does not allocate a node) when the token can never be
+    /// canceled, or when the token is already canceled -- in which case the
+    /// callback is executed immediately on the calling thread.
+    fn register_owned(self: &Arc<CancellationToken>, on_cancel: Box<FnOnce() + Send + 'static>)
+        -> Option<OwnedRegistration>
+    {
+        let token = self.clone();
+        let (node, on_cancel) = match Self::register_node(&token, on_cancel) {
+            Some(result) => result,
+            None => return None
+        };
+        Some(OwnedRegistration { node: node, on_cancel: on_cancel, token: token })
+    }
+
+    /// Borrowed counterpart of `register_owned`, used by the `cancelled()`
+    /// future: the returned handle borrows the token for `'a` instead of holding
+    /// an `Arc`. Semantics otherwise match `register_owned`.
+    #[cfg(feature = "async")]
+    fn register_borrowed<'a>(&'a self, on_cancel: Box<FnOnce() + Send + 'static>)
+        -> Option<BorrowedRegistration<'a>>
+    {
+        let (node, on_cancel) = match Self::register_node(self, on_cancel) {
+            Some(result) => result,
+            None => return None
+        };
+        Some(BorrowedRegistration { node: node, on_cancel: on_cancel, marker: PhantomData })
+    }
+
+    /// Returns a future that resolves when this token is canceled.
+    ///
+    /// The future borrows the token, so it is convenient in `select!`-style code
+    /// where the token outlives the awaited operation. Use `cancelled_owned` to
+    /// obtain a `'static` future that can be held across tasks outliving the
+    /// `CancellationTokenSource`.
+    ///
+    /// Only available when the `async` feature is enabled.
+    #[cfg(feature = "async")]
+    pub fn cancelled(&self) -> WaitForCancellation {
+        WaitForCancellation {
+            token: self,
+            state: WaitState::new(),
+            registration: None,
+            registered: false
+        }
+    }
+
+    /// `'static` variant of `cancelled` that keeps the token alive through its
+    /// `Arc`, so the returned future may outlive the `CancellationTokenSource`.
+    ///
+    /// Only available when the `async` feature is enabled.
+    #[cfg(feature = "async")]
+    pub fn cancelled_owned(self: &Arc<CancellationToken>) -> WaitForCancellationOwned {
+        WaitForCancellationOwned {
+            token: self.clone(),
+            state: WaitState::new(),
+            registration: None,
+            registered: false
+        }
+    }
+
+    /// Registers `on_cancel` to be executed when this token is canceled, and
+    /// returns an owned `Registration` handle that keeps the callback live until
+    /// it is dropped.
+    ///
+    /// Unlike `run()`, whose registration lifetime is tied to a stack frame, the
+    /// returned handle can be stored and moved around freely. Dropping it
+    /// unlinks the callback from the token.
+    ///
+    /// If the token is already canceled when this function is called, `on_cancel`
+    /// is executed immediately on the current thread and the returned handle is
+    /// inert. As in `run()`, a concurrent `cancel()` is serialized against the
+    /// handle's drop, so the callback never runs after the drop returns.
+    pub fn register<C>(self: &Arc<CancellationToken>, on_cancel: C) -> Registration
+        where C: FnOnce() + Send + 'static
+    {
+        Registration { node: self.register_owned(Box::new(on_cancel)) }
+    }
+
+    /// Creates a child `CancellationTokenSource` that is canceled when this
+    /// token is canceled.
+    ///
+    /// See `CancellationTokenSource::child` for details.
+    pub fn child_source(self: &Arc<CancellationToken>) -> CancellationTokenSource {
+        let child = CancellationTokenSource::new();
+        let child_token = child.token.clone();
+        let mut links = Vec::new();
+        // If this token is already canceled, `register_owned` cancels the child
+        // immediately and returns `None`, so the child ends up already canceled.
+        if let Some(reg) = self.register_owned(Box::new(move || child_token.cancel())) {
+            links.push(reg);
+        }
+        CancellationTokenSource { token: child.token, links: links }
+    }
 }
 
 impl fmt::Debug for CancellationTokenSource {
@@ -404,6 +1088,18 @@ impl fmt::Debug for CancellationToken {
     }
 }
 
+impl Drop for CancellationToken {
+    fn drop(&mut self) {
+        // Release the slot's strong reference to the reason allocated by
+        // `cancel_with_reason`, if any. No synchronization is needed: we have
+        // exclusive access while dropping.
+        let ptr = *self.reason.get_mut();
+        if !ptr.is_null() {
+            unsafe { mem::drop(Arc::from_raw(ptr as *const CancelReason)); }
+        }
+    }
+}
+
 impl ops::Deref for CancellationTokenSource {
     type Target = CancellationToken;
 
@@ -427,13 +1123,19 @@ impl error::Error for OperationCanceled {
 
 impl From<OperationCanceled> for io::Error {
     fn from(oc: OperationCanceled) -> Self {
-        io::Error::new(io::ErrorKind::TimedOut, oc)
+        // Map the cancellation cause to an appropriate error kind, so a timeout
+        // and a user abort can be told apart. A cancellation without a reason
+        // keeps the historical `TimedOut` kind.
+        let kind = oc.reason().map_or(io::ErrorKind::TimedOut, CancelReason::io_error_kind);
+        io::Error::new(kind, oc)
     }
 }
 
 #[cfg(test)]
 mod test {
     use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::mpsc;
+    use std::thread;
     use super::*;
 
     #[test]
@@ -458,7 +1160,7 @@ mod test {
         assert_eq!(Ok(()), cts.result());
         cts.cancel();
         assert_eq!(true, cts.is_canceled());
-        assert_eq!(Err(OperationCanceled), cts.result());
+        assert_eq!(Err(OperationCanceled::new()), cts.result());
     }
 
     fn expect(state: &AtomicUsize, expected_state: usize) {
@@ -466,6 +1168,217 @@ mod test {
         state.store(expected_state + 1, Ordering::Release);
     }
 
+    #[test]
+    fn child_canceled_by_parent() {
+        let parent = CancellationTokenSource::new();
+        let child = parent.child();
+        assert_eq!(false, child.is_canceled());
+        parent.cancel();
+        assert_eq!(true, child.is_canceled());
+    }
+
+    #[test]
+    fn child_cancel_does_not_affect_parent() {
+        let parent = CancellationTokenSource::new();
+        let child = parent.child();
+        child.cancel();
+        assert_eq!(true, child.is_canceled());
+        assert_eq!(false, parent.is_canceled());
+    }
+
+    #[test]
+    fn child_of_canceled_parent_is_canceled() {
+        let parent = CancellationTokenSource::new();
+        parent.cancel();
+        let child = parent.child();
+        assert_eq!(true, child.is_canceled());
+    }
+
+    #[test]
+    fn dropped_child_unlinks_from_parent() {
+        let parent = CancellationTokenSource::new();
+        drop(parent.child());
+        // The parent must no longer hold a registration for the dropped child;
+        // canceling it must not touch freed memory.
+        parent.cancel();
+        assert_eq!(true, parent.is_canceled());
+    }
+
+    #[test]
+    fn linked_canceled_by_any_parent() {
+        let a = CancellationTokenSource::new();
+        let b = CancellationTokenSource::new();
+        let linked = CancellationTokenSource::linked(&[a.token(), b.token()]);
+        assert_eq!(false, linked.is_canceled());
+        b.cancel();
+        assert_eq!(true, linked.is_canceled());
+        assert_eq!(false, a.is_canceled());
+    }
+
+    #[test]
+    fn linked_of_canceled_parent_is_canceled() {
+        let a = CancellationTokenSource::new();
+        let b = CancellationTokenSource::new();
+        a.cancel();
+        let linked = CancellationTokenSource::linked(&[a.token(), b.token()]);
+        assert_eq!(true, linked.is_canceled());
+    }
+
+    #[test]
+    fn dropped_linked_unlinks_from_parents() {
+        let a = CancellationTokenSource::new();
+        drop(CancellationTokenSource::linked(&[a.token()]));
+        a.cancel();
+        assert_eq!(true, a.is_canceled());
+    }
+
+    #[test]
+    fn register_runs_callback_on_cancel() {
+        use std::sync::Arc;
+        let cts = CancellationTokenSource::new();
+        let flag = Arc::new(AtomicBool::new(false));
+        let f = flag.clone();
+        let _reg = cts.token().register(move || f.store(true, Ordering::Release));
+        assert_eq!(false, flag.load(Ordering::Acquire));
+        cts.cancel();
+        assert_eq!(true, flag.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn register_on_already_canceled_runs_immediately() {
+        use std::sync::Arc;
+        let cts = CancellationTokenSource::new();
+        cts.cancel();
+        let flag = Arc::new(AtomicBool::new(false));
+        let f = flag.clone();
+        let _reg = cts.token().register(move || f.store(true, Ordering::Release));
+        assert_eq!(true, flag.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn dropped_registration_does_not_run() {
+        use std::sync::Arc;
+        let cts = CancellationTokenSource::new();
+        let flag = Arc::new(AtomicBool::new(false));
+        let f = flag.clone();
+        drop(cts.token().register(move || f.store(true, Ordering::Release)));
+        cts.cancel();
+        assert_eq!(false, flag.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn cancel_with_reason_is_retrievable() {
+        let cts = CancellationTokenSource::new();
+        assert!(cts.reason().is_none());
+        cts.cancel_with_reason(CancelReason::UserRequested);
+        assert!(cts.is_canceled());
+        match cts.reason() {
+            Some(&CancelReason::UserRequested) => {}
+            other => panic!("unexpected reason: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn plain_cancel_has_no_reason() {
+        let cts = CancellationTokenSource::new();
+        cts.cancel();
+        assert!(cts.reason().is_none());
+    }
+
+    #[test]
+    fn reason_is_not_overwritten() {
+        let cts = CancellationTokenSource::new();
+        cts.cancel_with_reason(CancelReason::TimedOut);
+        cts.cancel_with_reason(CancelReason::UserRequested);
+        match cts.reason() {
+            Some(r) => assert_eq!(io::ErrorKind::TimedOut, r.io_error_kind()),
+            None => panic!("expected a reason")
+        }
+    }
+
+    #[test]
+    fn custom_reason_can_be_downcast() {
+        let cts = CancellationTokenSource::new();
+        cts.cancel_with_reason(CancelReason::Custom(Box::new(42i32)));
+        match cts.reason() {
+            Some(&CancelReason::Custom(ref payload)) => {
+                assert_eq!(Some(&42i32), payload.downcast_ref::<i32>());
+            }
+            other => panic!("unexpected reason: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn try_cancel_succeeds_on_uncontended_token() {
+        let cts = CancellationTokenSource::new();
+        assert_eq!(true, cts.try_cancel());
+        assert_eq!(true, cts.is_canceled());
+        // A second attempt on an already-canceled token reports no transition.
+        assert_eq!(false, cts.try_cancel());
+    }
+
+    #[test]
+    fn try_cancel_with_reason_records_cause() {
+        let cts = CancellationTokenSource::new();
+        assert_eq!(true, cts.try_cancel_with_reason(CancelReason::UserRequested));
+        match cts.reason() {
+            Some(&CancelReason::UserRequested) => {}
+            other => panic!("unexpected reason: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn try_cancel_returns_false_while_registration_mutex_is_held() {
+        let cts = CancellationTokenSource::new();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        // `cancel_locked` holds the registration mutex for the whole walk over
+        // on_cancel callbacks, so blocking inside one keeps the mutex held.
+        let _registration = cts.token().register(move || {
+            ready_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        let token = cts.token().clone();
+        let canceler = thread::spawn(move || {
+            token.cancel();
+        });
+        ready_rx.recv().unwrap();
+        assert_eq!(false, cts.try_cancel());
+        release_tx.send(()).unwrap();
+        canceler.join().unwrap();
+        assert_eq!(true, cts.is_canceled());
+    }
+
+    #[test]
+    fn io_error_distinguishes_cause() {
+        let timed_out = CancellationTokenSource::new();
+        timed_out.cancel_with_reason(CancelReason::TimedOut);
+        let err: io::Error = timed_out.result().unwrap_err().into();
+        assert_eq!(io::ErrorKind::TimedOut, err.kind());
+
+        let aborted = CancellationTokenSource::new();
+        aborted.cancel_with_reason(CancelReason::UserRequested);
+        let err: io::Error = aborted.result().unwrap_err().into();
+        assert_eq!(io::ErrorKind::Interrupted, err.kind());
+
+        // A cancellation without a reason keeps the historical kind.
+        let plain = CancellationTokenSource::new();
+        plain.cancel();
+        let err: io::Error = plain.result().unwrap_err().into();
+        assert_eq!(io::ErrorKind::TimedOut, err.kind());
+    }
+
+    #[test]
+    fn result_error_carries_reason() {
+        let cts = CancellationTokenSource::new();
+        cts.cancel_with_reason(CancelReason::UserRequested);
+        let err = cts.result().unwrap_err();
+        match err.reason() {
+            Some(&CancelReason::UserRequested) => {}
+            other => panic!("unexpected reason: {:?}", other)
+        }
+    }
+
     #[test]
     fn run_already_canceled() {
         let cts = CancellationTokenSource::new();
@@ -538,3 +1451,78 @@ mod test {
             });
     }
 }
+
+#[cfg(all(test, feature = "async"))]
+mod async_test {
+    use super::*;
+    use std::future::Future;
+    use std::ptr;
+    use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    fn noop_waker() -> Waker {
+        fn no_op(_: *const ()) {}
+        fn clone(_: *const ()) -> RawWaker { RawWaker::new(ptr::null(), &VTABLE) }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, no_op, no_op, no_op);
+        unsafe { Waker::from_raw(RawWaker::new(ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn cancelled_resolves_on_cancel() {
+        let cts = CancellationTokenSource::new();
+        let mut fut = Box::pin(cts.cancelled());
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Pending, fut.as_mut().poll(&mut cx));
+        cts.cancel();
+        assert_eq!(Poll::Ready(()), fut.as_mut().poll(&mut cx));
+    }
+
+    #[test]
+    fn cancelled_owned_resolves_when_already_canceled() {
+        let cts = CancellationTokenSource::new();
+        cts.cancel();
+        let mut fut = Box::pin(cts.token().cancelled_owned());
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Ready(()), fut.as_mut().poll(&mut cx));
+    }
+
+    struct NeverReady;
+    impl Future for NeverReady {
+        type Output = i32;
+        fn poll(self: Pin<&mut Self>, _: &mut Context) -> Poll<i32> {
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn or_cancel_returns_ok_when_future_completes() {
+        let cts = CancellationTokenSource::new();
+        let mut fut = Box::pin(std::future::ready(42).or_cancel(cts.token()));
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Ready(Ok(42)), fut.as_mut().poll(&mut cx));
+    }
+
+    #[test]
+    fn or_cancel_returns_err_when_canceled() {
+        let cts = CancellationTokenSource::new();
+        let mut fut = Box::pin(NeverReady.or_cancel(cts.token()));
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Pending, fut.as_mut().poll(&mut cx));
+        cts.cancel();
+        assert_eq!(Poll::Ready(Err(OperationCanceled::new())), fut.as_mut().poll(&mut cx));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cancelable polled after completion")]
+    fn or_cancel_panics_if_polled_after_completion() {
+        let cts = CancellationTokenSource::new();
+        let mut fut = Box::pin(std::future::ready(1).or_cancel(cts.token()));
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        assert_eq!(Poll::Ready(Ok(1)), fut.as_mut().poll(&mut cx));
+        let _ = fut.as_mut().poll(&mut cx);
+    }
+}